@@ -1,7 +1,7 @@
 use crate::error::Error;
 use aiken_lang::{
     ast::{
-        DataType, DataTypeKey, Definition, Function, FunctionAccessKey, Located, ModuleKind,
+        DataType, DataTypeKey, Definition, Function, FunctionAccessKey, Located, ModuleKind, Span,
         Tracing, TypedDataType, TypedFunction, TypedModule, TypedValidator, UntypedModule,
         Validator,
     },
@@ -11,7 +11,7 @@ use aiken_lang::{
     tipo::TypeInfo,
 };
 use indexmap::IndexMap;
-use petgraph::{algo, graph::NodeIndex, Direction, Graph};
+use petgraph::{algo, graph::NodeIndex, visit::EdgeRef, Direction, Graph};
 use std::{
     collections::{HashMap, HashSet},
     ops::{Deref, DerefMut},
@@ -30,15 +30,10 @@ pub struct ParsedModule {
 }
 
 impl ParsedModule {
-    pub fn deps_for_graph(&self) -> (String, Vec<String>) {
+    pub fn deps_for_graph(&self) -> (String, Vec<(String, Span)>) {
         let name = self.name.clone();
 
-        let deps: Vec<_> = self
-            .ast
-            .dependencies()
-            .into_iter()
-            .map(|(dep, _span)| dep)
-            .collect();
+        let deps = self.ast.dependencies();
 
         (name, deps)
     }
@@ -47,16 +42,25 @@ impl ParsedModule {
 pub struct ParsedModules(HashMap<String, ParsedModule>);
 
 impl ParsedModules {
-    pub fn sequence(&self) -> Result<Vec<String>, Error> {
+    /// Build the module dependency graph, where an edge from module `a` to
+    /// module `b` means that `a` imports `b`, weighted by the source span of
+    /// the `use` import responsible for that edge.
+    fn dependency_graph(
+        &self,
+    ) -> (
+        Graph<(), Span>,
+        HashMap<String, NodeIndex>,
+        HashMap<NodeIndex, String>,
+    ) {
         let inputs = self
             .0
             .values()
             .map(|m| m.deps_for_graph())
-            .collect::<Vec<(String, Vec<String>)>>();
+            .collect::<Vec<(String, Vec<(String, Span)>)>>();
 
         let capacity = inputs.len();
 
-        let mut graph = Graph::<(), ()>::with_capacity(capacity, capacity * 5);
+        let mut graph = Graph::<(), Span>::with_capacity(capacity, capacity * 5);
 
         // TODO: maybe use a bimap?
         let mut indices = HashMap::with_capacity(capacity);
@@ -72,14 +76,20 @@ impl ParsedModules {
 
         for (value, deps) in inputs {
             if let Some(from_index) = indices.get(&value) {
-                let deps = deps.into_iter().filter_map(|dep| indices.get(&dep));
-
-                for to_index in deps {
-                    graph.add_edge(*from_index, *to_index, ());
+                for (dep, span) in deps {
+                    if let Some(to_index) = indices.get(&dep) {
+                        graph.add_edge(*from_index, *to_index, span);
+                    }
                 }
             }
         }
 
+        (graph, indices, values)
+    }
+
+    pub fn sequence(&self) -> Result<Vec<String>, Error> {
+        let (graph, _indices, mut values) = self.dependency_graph();
+
         match algo::toposort(&graph, None) {
             Ok(sequence) => {
                 let sequence = sequence
@@ -90,22 +100,93 @@ impl ParsedModules {
 
                 Ok(sequence)
             }
-            Err(cycle) => {
-                let origin = cycle.node_id();
+            Err(_cycle) => {
+                let cycles = find_cycles(&graph, &values);
+
+                Err(Error::ImportCycle { cycles })
+            }
+        }
+    }
 
-                let mut path = vec![];
+    /// Like [`ParsedModules::sequence`], but instead of a single flat order,
+    /// partitions the module DAG into dependency layers: every module in a
+    /// layer only depends on modules from previous layers, so all the
+    /// modules within a layer can be type-checked concurrently.
+    ///
+    /// A module's layer is its dependency depth — the length of its longest
+    /// chain of dependencies — not its ancestry depth, so a module with no
+    /// dependencies always lands in the first layer alongside every other
+    /// leaf, whether or not anything else depends on it.
+    pub fn sequence_layers(&self) -> Result<Vec<Vec<String>>, Error> {
+        let (graph, _indices, values) = self.dependency_graph();
+
+        layer_nodes(&graph, values).map_err(|cycles| Error::ImportCycle { cycles })
+    }
+}
 
-                find_cycle(origin, origin, &graph, &mut path, &mut HashSet::new());
+/// Partition a dependency graph (edges point from an importer to its
+/// dependency) into layers by dependency depth, via Kahn's algorithm: a node
+/// joins the frontier once every one of its dependencies has already been
+/// placed in an earlier layer, so nodes with no dependencies at all start in
+/// the first layer regardless of how many (if any) other nodes depend on
+/// them.
+fn layer_nodes(
+    graph: &Graph<(), Span>,
+    mut values: HashMap<NodeIndex, String>,
+) -> Result<Vec<Vec<String>>, Vec<Vec<(String, Span)>>> {
+    let mut remaining_deps: HashMap<NodeIndex, usize> = graph
+        .node_indices()
+        .map(|index| {
+            (
+                index,
+                graph.neighbors_directed(index, Direction::Outgoing).count(),
+            )
+        })
+        .collect();
 
-                let modules = path
-                    .iter()
-                    .filter_map(|index| values.remove(index))
-                    .collect();
+    let mut remaining = graph.node_count();
+    let mut layers = Vec::new();
+
+    let mut frontier: Vec<NodeIndex> = remaining_deps
+        .iter()
+        .filter(|(_, count)| **count == 0)
+        .map(|(index, _)| *index)
+        .collect();
+
+    while !frontier.is_empty() {
+        remaining -= frontier.len();
+
+        let mut layer: Vec<String> = frontier
+            .iter()
+            .filter_map(|index| values.get(index).cloned())
+            .collect();
+        layer.sort();
+        layers.push(layer);
 
-                Err(Error::ImportCycle { modules })
+        let mut next_frontier = Vec::new();
+
+        for index in &frontier {
+            // Modules that import `index` have one fewer unresolved
+            // dependency now that it has been placed in a layer.
+            for dependent in graph.neighbors_directed(*index, Direction::Incoming) {
+                if let Some(count) = remaining_deps.get_mut(&dependent) {
+                    *count -= 1;
+
+                    if *count == 0 {
+                        next_frontier.push(dependent);
+                    }
+                }
             }
         }
+
+        frontier = next_frontier;
+    }
+
+    if remaining > 0 {
+        return Err(find_cycles(graph, &values));
     }
+
+    Ok(layers)
 }
 
 impl From<HashMap<String, ParsedModule>> for ParsedModules {
@@ -134,34 +215,304 @@ impl DerefMut for ParsedModules {
     }
 }
 
-fn find_cycle(
-    origin: NodeIndex,
-    parent: NodeIndex,
-    graph: &petgraph::Graph<(), ()>,
-    path: &mut Vec<NodeIndex>,
-    seen: &mut HashSet<NodeIndex>,
-) -> bool {
-    seen.insert(parent);
+/// Find every non-trivial strongly-connected component of the dependency
+/// graph in one pass, and report each as a list of `(module_name, use_span)`
+/// pairs, one per member: the span on each entry points at a `use` import
+/// from that module to another member of the component. When the component
+/// is a single simple cycle, the list is an ordered ring and each span leads
+/// to the next module in it; otherwise the walk below has to jump between
+/// members, and an entry followed by a jump does not lead to the next entry.
+fn find_cycles(
+    graph: &Graph<(), Span>,
+    values: &HashMap<NodeIndex, String>,
+) -> Vec<Vec<(String, Span)>> {
+    algo::tarjan_scc(graph)
+        .into_iter()
+        .filter(|component| {
+            component.len() > 1
+                || component
+                    .first()
+                    .map(|&node| graph.find_edge(node, node).is_some())
+                    .unwrap_or(false)
+        })
+        .map(|component| {
+            let members: HashSet<NodeIndex> = component.iter().copied().collect();
+
+            // `tarjan_scc`'s component order doesn't follow the cycle, so
+            // walk the ring itself: start anywhere in the component and
+            // repeatedly step to the next member via an edge that stays
+            // within it, preferring one we haven't visited yet so the ring
+            // reads as a coherent walk where possible. A component isn't
+            // always a single Hamiltonian cycle though (e.g. `A->B, A->C,
+            // B->D, C->D, D->A` is one SCC but no simple ring visits all
+            // four), so once the walk runs out of unvisited neighbours to
+            // step to, jump to any member it hasn't covered yet and resume
+            // from there. Every member of a non-trivial SCC has at least
+            // one outgoing edge back into the component, so this always
+            // terminates with exactly one entry per member.
+            let mut visited = HashSet::new();
+            let mut ring = Vec::with_capacity(component.len());
+            let mut node = component[0];
+
+            while visited.len() < members.len() {
+                visited.insert(node);
+
+                let candidates: Vec<_> = graph
+                    .edges_directed(node, Direction::Outgoing)
+                    .filter(|edge| members.contains(&edge.target()))
+                    .collect();
 
-    for node in graph.neighbors_directed(parent, Direction::Outgoing) {
-        if node == origin {
-            path.push(node);
+                let edge = candidates
+                    .iter()
+                    .find(|edge| !visited.contains(&edge.target()))
+                    .or_else(|| candidates.first())
+                    .expect("scc member has an outgoing edge within the component");
+
+                let name = values.get(&node).cloned().unwrap_or_default();
+                ring.push((name, *edge.weight()));
+
+                node = if visited.len() < members.len() && visited.contains(&edge.target()) {
+                    *members
+                        .iter()
+                        .find(|member| !visited.contains(member))
+                        .expect("an unvisited member remains")
+                } else {
+                    edge.target()
+                };
+            }
 
-            return true;
+            ring
+        })
+        .collect()
+}
+
+/// Scan a doc comment for bracketed intra-doc link syntax (`[Name]`,
+/// `[module.function]`), skipping regular markdown links (`[text](url)`),
+/// and resolve each one found.
+fn extract_doc_links(
+    doc: &str,
+    from_module: &str,
+    aliases: &HashMap<String, String>,
+    modules: &CheckedModules,
+) -> Vec<DocLink> {
+    let mut links = Vec::new();
+    let mut chars = doc.char_indices().peekable();
+
+    while let Some((start, ch)) = chars.next() {
+        if ch != '[' {
+            continue;
         }
 
-        if seen.contains(&node) {
+        let end = chars.by_ref().find(|(_, c)| *c == ']').map(|(i, _)| i);
+
+        let Some(end) = end else {
+            break;
+        };
+
+        // A markdown link `[text](url)` isn't an intra-doc reference.
+        if doc[end + 1..].starts_with('(') {
             continue;
         }
 
-        if find_cycle(origin, node, graph, path, seen) {
-            path.push(node);
+        let raw = doc[start + 1..end].to_string();
+        let target = resolve_doc_link(&raw, from_module, aliases, modules);
+
+        links.push(DocLink { raw, target });
+    }
+
+    links
+}
+
+fn resolve_doc_link(
+    raw: &str,
+    from_module: &str,
+    aliases: &HashMap<String, String>,
+    modules: &CheckedModules,
+) -> Option<DocLinkTarget> {
+    match raw.rsplit_once('.') {
+        Some((alias, name)) => {
+            let module = aliases
+                .get(alias)
+                .cloned()
+                .unwrap_or_else(|| alias.to_string());
+
+            find_definition(modules, &module, name)
+        }
+        None => find_definition(modules, from_module, raw),
+    }
+}
+
+fn find_definition(modules: &CheckedModules, module: &str, name: &str) -> Option<DocLinkTarget> {
+    let checked_module = modules.get(module)?;
+
+    for def in checked_module.ast.definitions() {
+        let definition_name = match def {
+            Definition::Fn(Function { name, .. }) => Some(name.as_str()),
+            Definition::DataType(DataType { name, .. }) => Some(name.as_str()),
+            Definition::Validator(Validator { fun, .. }) => Some(fun.name.as_str()),
+            Definition::TypeAlias(alias) => Some(alias.alias.as_str()),
+            Definition::ModuleConstant(constant) => Some(constant.name.as_str()),
+            Definition::Use(_) | Definition::Test(_) => None,
+        };
+
+        if definition_name == Some(name) {
+            return Some(DocLinkTarget {
+                module: module.to_string(),
+                name: name.to_string(),
+                location: def.location(),
+            });
+        }
+
+        if let Definition::DataType(DataType { constructors, .. }) = def {
+            if let Some(constructor) = constructors.iter().find(|c| c.name == name) {
+                return Some(DocLinkTarget {
+                    module: module.to_string(),
+                    name: name.to_string(),
+                    location: constructor.location,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// One `use` of a particular module already present in a from-module's
+/// source, reduced to just what [`decide_reference`] needs: the alias the
+/// qualified form would use, and the names brought into scope unqualified.
+struct UseOccurrence {
+    alias: String,
+    unqualified: Vec<(String, Option<String>)>,
+}
 
-            return true;
+/// Decide the shortest already-in-scope reference to `target_name`, given
+/// every `use` of its module already present in the source: an unqualified
+/// import (under its own alias, if any) beats the qualified `alias.name`
+/// form. Every occurrence is checked, not just the first, since a later
+/// `use` of the same module may import the name selectively even if an
+/// earlier one only imports it qualified. Returns `None` when `target_name`
+/// isn't reachable through any of the given occurrences, meaning a new
+/// `use` is needed.
+fn decide_reference(target_name: &str, occurrences: &[UseOccurrence]) -> Option<String> {
+    for occurrence in occurrences {
+        if let Some((_, as_name)) = occurrence
+            .unqualified
+            .iter()
+            .find(|(name, _)| name == target_name)
+        {
+            return Some(as_name.clone().unwrap_or_else(|| target_name.to_string()));
         }
     }
 
-    false
+    occurrences
+        .iter()
+        .map(|occurrence| format!("{}.{}", occurrence.alias, target_name))
+        .min_by_key(String::len)
+}
+
+/// A reference to another definition named inside a doc comment via
+/// intra-doc link syntax, e.g. `[SomeType]` or `[module.function]`.
+#[derive(Debug, Clone)]
+pub struct DocLink {
+    /// The link text as written between the brackets, e.g. `module.function`.
+    pub raw: String,
+    /// The definition it resolves to, or `None` if it couldn't be resolved
+    /// (e.g. a typo), so doc rendering can flag it rather than fail.
+    pub target: Option<DocLinkTarget>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DocLinkTarget {
+    pub module: String,
+    pub name: String,
+    pub location: Span,
+}
+
+/// How a symbol can be referenced from a given module, as computed by
+/// [`CheckedModules::import_suggestion`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportSuggestion {
+    /// Already in scope (possibly under an alias); use this text as-is.
+    InScope { reference: String },
+    /// Not in scope; use `reference`, after adding `use_line`.
+    NeedsImport { reference: String, use_line: String },
+}
+
+/// One definition that a name in a [`SymbolIndex`] refers to.
+#[derive(Debug, Clone)]
+pub struct SymbolEntry {
+    pub module: String,
+    pub name: String,
+    pub location: Span,
+}
+
+/// A queryable index over every named definition across a set of
+/// [`CheckedModules`], keyed by unqualified name. Backs LSP features such as
+/// go-to-definition across modules, workspace symbol search, and auto-import
+/// candidates without re-scanning every module's AST on each request.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndex {
+    by_name: HashMap<String, Vec<SymbolEntry>>,
+}
+
+impl SymbolIndex {
+    /// Insert a definition, keeping its name group sorted by module so that
+    /// `by_name` and `search` return a deterministic order rather than one
+    /// that jitters with `HashMap` iteration order between rebuilds.
+    fn insert(&mut self, name: String, module: String, location: Span) {
+        let entries = self.by_name.entry(name.clone()).or_default();
+
+        let entry = SymbolEntry {
+            module,
+            name,
+            location,
+        };
+
+        let position = entries
+            .binary_search_by(|existing| {
+                (&existing.module, &existing.name).cmp(&(&entry.module, &entry.name))
+            })
+            .unwrap_or_else(|i| i);
+
+        entries.insert(position, entry);
+    }
+
+    /// Every definition named exactly `name`, across all modules, sorted by
+    /// module.
+    pub fn by_name(&self, name: &str) -> &[SymbolEntry] {
+        self.by_name.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Every definition in a given module, sorted by name then module to
+    /// match `by_name` and `search` rather than `HashMap` iteration order.
+    pub fn in_module(&self, module: &str) -> Vec<&SymbolEntry> {
+        let mut results: Vec<&SymbolEntry> = self
+            .by_name
+            .values()
+            .flatten()
+            .filter(|entry| entry.module == module)
+            .collect();
+
+        results.sort_by(|a, b| (&a.name, &a.module).cmp(&(&b.name, &b.module)));
+
+        results
+    }
+
+    /// Every definition whose name starts with `prefix`, for editor
+    /// autocompletion and auto-import suggestions, sorted by name then
+    /// module.
+    pub fn search(&self, prefix: &str) -> Vec<&SymbolEntry> {
+        let mut results: Vec<&SymbolEntry> = self
+            .by_name
+            .iter()
+            .filter(|(name, _)| name.starts_with(prefix))
+            .flat_map(|(_, entries)| entries)
+            .collect();
+
+        results.sort_by(|a, b| (&a.name, &a.module).cmp(&(&b.name, &b.module)));
+
+        results
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -173,6 +524,18 @@ pub struct CheckedModule {
     pub package: String,
     pub ast: TypedModule,
     pub extra: ModuleExtra,
+    /// Intra-doc links found in definition doc comments, keyed by the
+    /// source location of the doc-bearing definition. Populated by
+    /// [`resolve_doc_links`], which runs after every module has been
+    /// type-checked.
+    ///
+    /// [`resolve_doc_links`]: CheckedModules::resolve_doc_links
+    pub doc_links: HashMap<usize, Vec<DocLink>>,
+    /// Intra-doc links found in the module-level doc comment (`self.ast.docs`),
+    /// also populated by [`resolve_doc_links`].
+    ///
+    /// [`resolve_doc_links`]: CheckedModules::resolve_doc_links
+    pub module_doc_links: Vec<DocLink>,
 }
 
 impl CheckedModule {
@@ -180,6 +543,56 @@ impl CheckedModule {
         self.ast.find_node(byte_index)
     }
 
+    /// Parse intra-doc link syntax (e.g. `[SomeType]`, `[module.function]`,
+    /// `[Constructor]`) out of doc comments already attached by
+    /// [`attach_doc_and_module_comments`] — both the module-level doc
+    /// comment and every definition's doc comment — and resolve each one to
+    /// the definition it names, honoring this module's `use` aliases and
+    /// falling back to the local module scope when unqualified.
+    ///
+    /// Unresolved links are kept with `target: None` rather than erroring,
+    /// so a typo in a doc comment never fails documentation generation.
+    ///
+    /// [`attach_doc_and_module_comments`]: CheckedModule::attach_doc_and_module_comments
+    pub fn resolve_doc_links(&mut self, modules: &CheckedModules) {
+        let aliases = self.use_aliases();
+
+        let module_doc = self.ast.docs.join("\n");
+        self.module_doc_links = extract_doc_links(&module_doc, &self.name, &aliases, modules);
+
+        let mut doc_links = HashMap::new();
+
+        for def in self.ast.definitions() {
+            if let Some(doc) = def.doc() {
+                let links = extract_doc_links(doc, &self.name, &aliases, modules);
+
+                if !links.is_empty() {
+                    doc_links.insert(def.location().start, links);
+                }
+            }
+        }
+
+        self.doc_links = doc_links;
+    }
+
+    fn use_aliases(&self) -> HashMap<String, String> {
+        self.ast
+            .definitions()
+            .filter_map(|def| match def {
+                Definition::Use(u) => {
+                    let target = u.module.join("/");
+                    let alias = u
+                        .as_name
+                        .clone()
+                        .unwrap_or_else(|| u.module.last().cloned().unwrap_or_default());
+
+                    Some((alias, target))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn attach_doc_and_module_comments(&mut self) {
         // Module Comments
         self.ast.docs = self
@@ -319,6 +732,118 @@ impl CheckedModules {
         modules
     }
 
+    /// Resolve intra-doc links in every module's doc comments, now that all
+    /// modules are type-checked and can be cross-referenced. Run this once,
+    /// after the whole project has been checked.
+    pub fn resolve_doc_links(&mut self) {
+        let snapshot = self.clone();
+
+        for module in self.0.values_mut() {
+            module.resolve_doc_links(&snapshot);
+        }
+    }
+
+    /// Build a [`SymbolIndex`] over every function, data type, constructor,
+    /// validator, type alias and constant defined across these modules, by
+    /// walking each module's definitions once. Cheap enough to rebuild
+    /// whenever a single module changes, rather than caching incrementally.
+    pub fn symbol_index(&self) -> SymbolIndex {
+        let mut index = SymbolIndex::default();
+
+        for module in self.0.values() {
+            for def in module.ast.definitions() {
+                let location = def.location();
+
+                match def {
+                    Definition::Fn(Function { name, .. }) => {
+                        index.insert(name.clone(), module.name.clone(), location);
+                    }
+                    Definition::DataType(DataType {
+                        name, constructors, ..
+                    }) => {
+                        index.insert(name.clone(), module.name.clone(), location);
+
+                        for constructor in constructors {
+                            index.insert(
+                                constructor.name.clone(),
+                                module.name.clone(),
+                                constructor.location,
+                            );
+                        }
+                    }
+                    Definition::Validator(Validator { fun, .. }) => {
+                        index.insert(fun.name.clone(), module.name.clone(), location);
+                    }
+                    Definition::TypeAlias(alias) => {
+                        index.insert(alias.alias.clone(), module.name.clone(), location);
+                    }
+                    Definition::ModuleConstant(constant) => {
+                        index.insert(constant.name.clone(), module.name.clone(), location);
+                    }
+                    Definition::Use(_) | Definition::Test(_) => {}
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Compute the shortest syntactically valid way to reference the
+    /// definition named `target_name` in `target_module` from
+    /// `from_module`'s source: reusing an existing `use` alias (or
+    /// unqualified import) when the symbol is already in scope, or else the
+    /// qualified `module.name` form plus the `use` statement that would need
+    /// to be added.
+    ///
+    /// This powers an "auto-import" quick-fix: when a user writes an
+    /// unqualified name that isn't resolvable locally, the tool can offer
+    /// the exact `use` line to insert plus the reference text for the call
+    /// site.
+    pub fn import_suggestion(
+        &self,
+        from_module: &str,
+        target_module: &str,
+        target_name: &str,
+    ) -> Option<ImportSuggestion> {
+        if from_module == target_module {
+            return Some(ImportSuggestion::InScope {
+                reference: target_name.to_string(),
+            });
+        }
+
+        let from = self.0.get(from_module)?;
+
+        let occurrences: Vec<UseOccurrence> = from
+            .ast
+            .definitions()
+            .filter_map(|def| match def {
+                Definition::Use(u) if u.module.join("/") == target_module => Some(UseOccurrence {
+                    alias: u
+                        .as_name
+                        .clone()
+                        .unwrap_or_else(|| u.module.last().cloned().unwrap_or_default()),
+                    unqualified: u
+                        .unqualified
+                        .iter()
+                        .map(|import| (import.name.clone(), import.as_name.clone()))
+                        .collect(),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        if let Some(reference) = decide_reference(target_name, &occurrences) {
+            return Some(ImportSuggestion::InScope { reference });
+        }
+
+        let module_alias = target_module.rsplit('/').next().unwrap_or(target_module);
+
+        Some(ImportSuggestion::NeedsImport {
+            reference: format!("{module_alias}.{target_name}"),
+            use_line: format!("use {target_module}"),
+        })
+    }
+
     pub fn validators(&self) -> impl Iterator<Item = (&CheckedModule, &TypedValidator)> {
         let mut items = vec![];
 
@@ -435,3 +960,275 @@ impl DerefMut for CheckedModules {
         &mut self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    #[test]
+    fn find_cycles_reports_an_ordered_ring() {
+        let mut graph = Graph::<(), Span>::new();
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+
+        graph.add_edge(a, b, span(0, 1));
+        graph.add_edge(b, c, span(1, 2));
+        graph.add_edge(c, a, span(2, 3));
+
+        let mut values = HashMap::new();
+        values.insert(a, "a".to_string());
+        values.insert(b, "b".to_string());
+        values.insert(c, "c".to_string());
+
+        let cycles = find_cycles(&graph, &values);
+
+        assert_eq!(cycles.len(), 1);
+
+        let ring = &cycles[0];
+        assert_eq!(ring.len(), 3);
+
+        let index_of = |name: &str| match name {
+            "a" => a,
+            "b" => b,
+            "c" => c,
+            _ => unreachable!(),
+        };
+
+        // Each entry's span must be the edge leading to the *next* entry in
+        // the ring, not just some arbitrary in-component edge.
+        for i in 0..ring.len() {
+            let (from, used_span) = &ring[i];
+            let (to, _) = &ring[(i + 1) % ring.len()];
+
+            let expected_edge = graph.find_edge(index_of(from), index_of(to));
+            let expected_span = expected_edge.map(|edge| graph[edge]);
+
+            assert_eq!(Some(*used_span), expected_span);
+        }
+    }
+
+    #[test]
+    fn find_cycles_covers_every_member_of_a_branching_component() {
+        // `a` can reach the rest of the component two ways (`a->b` and
+        // `a->c`), so a greedy ring-walk that stops as soon as it gets back
+        // to `a` (e.g. via `a->b->d->a`) would leave `c` out entirely, even
+        // though every node here reaches every other one.
+        let mut graph = Graph::<(), Span>::new();
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+
+        graph.add_edge(a, b, span(0, 1));
+        graph.add_edge(a, c, span(1, 2));
+        graph.add_edge(b, d, span(2, 3));
+        graph.add_edge(c, d, span(3, 4));
+        graph.add_edge(d, a, span(4, 5));
+
+        let mut values = HashMap::new();
+        values.insert(a, "a".to_string());
+        values.insert(b, "b".to_string());
+        values.insert(c, "c".to_string());
+        values.insert(d, "d".to_string());
+
+        let cycles = find_cycles(&graph, &values);
+
+        assert_eq!(cycles.len(), 1);
+
+        let ring = &cycles[0];
+        assert_eq!(ring.len(), 4);
+
+        let index_of = |name: &str| match name {
+            "a" => a,
+            "b" => b,
+            "c" => c,
+            "d" => d,
+            _ => unreachable!(),
+        };
+
+        let names: HashSet<&str> = ring.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, HashSet::from(["a", "b", "c", "d"]));
+
+        let members = [a, b, c, d];
+
+        // Every reported entry must still be a genuine edge out of that
+        // node and into some other member of the component.
+        for (name, used_span) in ring {
+            let is_genuine_edge = graph
+                .edges_directed(index_of(name), Direction::Outgoing)
+                .any(|edge| *edge.weight() == *used_span && members.contains(&edge.target()));
+
+            assert!(is_genuine_edge);
+        }
+    }
+
+    #[test]
+    fn sequence_layers_puts_standalone_modules_in_the_first_layer() {
+        let mut graph = Graph::<(), Span>::new();
+        let p1 = graph.add_node(());
+        let p2 = graph.add_node(());
+        let p3 = graph.add_node(());
+        let p4 = graph.add_node(());
+        let e = graph.add_node(());
+
+        graph.add_edge(p1, p2, span(0, 1));
+        graph.add_edge(p2, p3, span(0, 1));
+        graph.add_edge(p3, p4, span(0, 1));
+
+        let mut values = HashMap::new();
+        values.insert(p1, "p1".to_string());
+        values.insert(p2, "p2".to_string());
+        values.insert(p3, "p3".to_string());
+        values.insert(p4, "p4".to_string());
+        values.insert(e, "e".to_string());
+
+        let layers = layer_nodes(&graph, values).unwrap();
+
+        assert_eq!(
+            layers,
+            vec![
+                vec!["e".to_string(), "p4".to_string()],
+                vec!["p3".to_string()],
+                vec!["p2".to_string()],
+                vec!["p1".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn symbol_index_returns_entries_in_a_stable_order() {
+        let mut index = SymbolIndex::default();
+
+        index.insert("map".to_string(), "list".to_string(), span(10, 20));
+        index.insert("map".to_string(), "dict".to_string(), span(30, 40));
+        index.insert("map".to_string(), "array".to_string(), span(50, 60));
+
+        let modules: Vec<&str> = index
+            .by_name("map")
+            .iter()
+            .map(|entry| entry.module.as_str())
+            .collect();
+
+        assert_eq!(modules, vec!["array", "dict", "list"]);
+    }
+
+    #[test]
+    fn symbol_index_in_module_returns_entries_in_a_stable_order() {
+        let mut index = SymbolIndex::default();
+
+        index.insert("map".to_string(), "list".to_string(), span(10, 20));
+        index.insert("zip".to_string(), "list".to_string(), span(30, 40));
+        index.insert("filter".to_string(), "list".to_string(), span(50, 60));
+        index.insert("map".to_string(), "dict".to_string(), span(70, 80));
+
+        let names: Vec<&str> = index
+            .in_module("list")
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect();
+
+        assert_eq!(names, vec!["filter", "map", "zip"]);
+    }
+
+    #[test]
+    fn extract_doc_links_finds_and_leaves_unresolved_links_intact() {
+        let modules = CheckedModules::default();
+        let aliases = HashMap::new();
+
+        let links = extract_doc_links(
+            "See [NotDefined] for details, or the [markdown](https://example.com) link.",
+            "my_module",
+            &aliases,
+            &modules,
+        );
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].raw, "NotDefined");
+        assert!(links[0].target.is_none());
+    }
+
+    #[test]
+    fn import_suggestion_same_module_is_already_in_scope() {
+        let modules = CheckedModules::default();
+
+        let suggestion = modules.import_suggestion("my_module", "my_module", "foo");
+
+        assert_eq!(
+            suggestion,
+            Some(ImportSuggestion::InScope {
+                reference: "foo".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn decide_reference_prefers_an_unqualified_import() {
+        let occurrences = vec![UseOccurrence {
+            alias: "list".to_string(),
+            unqualified: vec![("map".to_string(), None)],
+        }];
+
+        assert_eq!(
+            decide_reference("map", &occurrences),
+            Some("map".to_string())
+        );
+    }
+
+    #[test]
+    fn decide_reference_honors_an_unqualified_import_alias() {
+        let occurrences = vec![UseOccurrence {
+            alias: "list".to_string(),
+            unqualified: vec![("map".to_string(), Some("list_map".to_string()))],
+        }];
+
+        assert_eq!(
+            decide_reference("map", &occurrences),
+            Some("list_map".to_string())
+        );
+    }
+
+    #[test]
+    fn decide_reference_falls_back_to_the_qualified_form() {
+        let occurrences = vec![UseOccurrence {
+            alias: "list".to_string(),
+            unqualified: vec![],
+        }];
+
+        assert_eq!(
+            decide_reference("map", &occurrences),
+            Some("list.map".to_string())
+        );
+    }
+
+    #[test]
+    fn decide_reference_checks_every_use_of_the_same_module() {
+        // The first `use` of the module only imports it qualified; a later
+        // `use` of that same module imports `map` selectively, and that
+        // shorter reference must win even though it isn't the first match.
+        let occurrences = vec![
+            UseOccurrence {
+                alias: "list".to_string(),
+                unqualified: vec![],
+            },
+            UseOccurrence {
+                alias: "l".to_string(),
+                unqualified: vec![("map".to_string(), None)],
+            },
+        ];
+
+        assert_eq!(
+            decide_reference("map", &occurrences),
+            Some("map".to_string())
+        );
+    }
+
+    #[test]
+    fn decide_reference_with_no_occurrences_needs_import() {
+        assert_eq!(decide_reference("map", &[]), None);
+    }
+}